@@ -0,0 +1,96 @@
+// Headless mode: run the same service supervisor and shutdown routine
+// used by the GUI, but without opening a Tauri window. Useful on CI or a
+// plain dev server where there's no display to show the webview on.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use clap::Parser;
+
+use crate::logs::LogSink;
+use crate::services::{is_service_ready, ServiceConfig};
+use crate::shutdown::shutdown_services;
+use crate::start_services;
+use crate::supervisor::{start_supervisor, AppState};
+
+#[derive(Parser, Debug)]
+#[command(name = "pharmacypro-launcher")]
+pub struct Cli {
+    /// Run as a pure service supervisor with no webview: start the
+    /// configured services, stream their logs to this terminal, and wait
+    /// for Ctrl-C to shut them down.
+    #[arg(long)]
+    pub headless: bool,
+}
+
+pub fn run_headless(manifest: Vec<ServiceConfig>) {
+    let runtime = tokio::runtime::Runtime::new().expect("failed to start tokio runtime");
+    runtime.block_on(run(manifest));
+}
+
+const MAX_READY_ATTEMPTS: u32 = 60; // 60 seconds max, same cap as the GUI's loading screen
+
+async fn run(manifest: Vec<ServiceConfig>) {
+    let state = Arc::new(Mutex::new(AppState::new()));
+
+    {
+        let state = Arc::clone(&state);
+        let manifest = manifest.clone();
+        tokio::task::spawn_blocking(move || {
+            start_services(&LogSink::Terminal, &manifest, &state);
+            start_supervisor(LogSink::Terminal, state);
+        });
+    }
+
+    println!("Waiting for all services to become healthy...");
+    let ready = tokio::select! {
+        ready = wait_until_ready(&manifest) => ready,
+        _ = tokio::signal::ctrl_c() => {
+            println!("Received Ctrl-C while waiting for services, shutting down...");
+            shutdown_services(&state, &manifest);
+            return;
+        }
+    };
+
+    if !ready {
+        eprintln!(
+            "Services failed to become healthy within {} seconds, shutting down",
+            MAX_READY_ATTEMPTS
+        );
+        shutdown_services(&state, &manifest);
+        std::process::exit(1);
+    }
+
+    println!("All services ready:");
+    for config in &manifest {
+        println!("  - {} (port {})", config.name, config.port);
+    }
+
+    tokio::signal::ctrl_c()
+        .await
+        .expect("failed to listen for ctrl-c");
+    println!("Received Ctrl-C, shutting down...");
+    shutdown_services(&state, &manifest);
+}
+
+async fn wait_until_ready(manifest: &[ServiceConfig]) -> bool {
+    for attempt in 1..=MAX_READY_ATTEMPTS {
+        let mut pending: Vec<&str> = Vec::new();
+        for config in manifest {
+            if !is_service_ready(config).await {
+                pending.push(&config.name);
+            }
+        }
+        if pending.is_empty() {
+            return true;
+        }
+        println!(
+            "Waiting for: {} ({}/{})",
+            pending.join(", "),
+            attempt,
+            MAX_READY_ATTEMPTS
+        );
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    false
+}
@@ -0,0 +1,115 @@
+// Streams a spawned service's stdout/stderr somewhere useful instead of
+// letting the piped output sit unread (which would otherwise eventually
+// block the child once its pipe buffer filled up). The GUI path emits
+// events for the webview; the headless CLI path just prints to the
+// terminal it was launched from.
+
+use std::io::{BufRead, BufReader};
+use std::process::Child;
+use std::thread;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceLogEvent {
+    pub service: String,
+    pub stream: &'static str,
+    pub line: String,
+}
+
+/// Emitted once when a supervised child exits, so the UI can tell a clean
+/// shutdown apart from a crash.
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceExitedEvent {
+    pub service: String,
+    pub exit_code: Option<i32>,
+    pub clean: bool,
+}
+
+/// Emitted when a service has crash-looped past the restart cap and the
+/// supervisor has given up on it.
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceFailedEvent {
+    pub service: String,
+    pub restart_count: u32,
+}
+
+#[derive(Clone)]
+pub enum LogSink {
+    TauriEvent(AppHandle),
+    Terminal,
+}
+
+impl LogSink {
+    fn publish(&self, event: ServiceLogEvent) {
+        match self {
+            LogSink::TauriEvent(app_handle) => {
+                let _ = app_handle.emit("service-log", event);
+            }
+            LogSink::Terminal => {
+                println!("[{}:{}] {}", event.service, event.stream, event.line);
+            }
+        }
+    }
+
+    pub fn publish_exited(&self, event: ServiceExitedEvent) {
+        match self {
+            LogSink::TauriEvent(app_handle) => {
+                let _ = app_handle.emit("service-exited", event);
+            }
+            LogSink::Terminal => {
+                println!(
+                    "[{}] exited (code: {:?}, clean: {})",
+                    event.service, event.exit_code, event.clean
+                );
+            }
+        }
+    }
+
+    pub fn publish_failed(&self, event: ServiceFailedEvent) {
+        match self {
+            LogSink::TauriEvent(app_handle) => {
+                let _ = app_handle.emit("service-failed", event);
+            }
+            LogSink::Terminal => {
+                println!(
+                    "[{}] gave up after {} restarts",
+                    event.service, event.restart_count
+                );
+            }
+        }
+    }
+}
+
+/// Spawn one reader thread per pipe on `child` (stdout and stderr) that
+/// publishes each line via `sink`. Must be called right after
+/// `spawn_service`, before the `Child` is moved into shared state.
+pub fn stream_child_output(sink: LogSink, service: &str, child: &mut Child) {
+    if let Some(stdout) = child.stdout.take() {
+        spawn_reader(sink.clone(), service.to_string(), stdout, "stdout");
+    }
+    if let Some(stderr) = child.stderr.take() {
+        spawn_reader(sink, service.to_string(), stderr, "stderr");
+    }
+}
+
+fn spawn_reader<R>(sink: LogSink, service: String, pipe: R, stream: &'static str)
+where
+    R: std::io::Read + Send + 'static,
+{
+    thread::spawn(move || {
+        let reader = BufReader::new(pipe);
+        for line in reader.lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(_) => break,
+            };
+            sink.publish(ServiceLogEvent {
+                service: service.clone(),
+                stream,
+                line,
+            });
+        }
+    });
+}
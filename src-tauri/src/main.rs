@@ -1,181 +1,156 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::process::{Child, Command, Stdio};
+mod cli;
+mod logs;
+mod port_probe;
+mod services;
+mod shutdown;
+mod supervisor;
+
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use clap::Parser;
 use tauri::{Manager, WebviewWindow};
 
-// Check if the backend is ready
-async fn is_backend_ready() -> bool {
-    match reqwest::get("http://127.0.0.1:8000/docs").await {
-        Ok(response) => response.status().is_success(),
-        Err(_) => false,
-    }
-}
+use cli::Cli;
+use logs::{stream_child_output, LogSink};
+use port_probe::is_port_listening;
+use services::{is_service_ready, load_manifest, spawn_service, ServiceConfig};
+use shutdown::shutdown_services;
+use supervisor::{force_restart, start_supervisor, AppState, ManagedService};
 
-// Check if the frontend is ready
-async fn is_frontend_ready() -> bool {
-    match reqwest::get("http://127.0.0.1:3000").await {
-        Ok(response) => response.status().is_success(),
-        Err(_) => false,
-    }
+const MANIFEST_PATH: &str = "services.yaml";
+// 60s at 500ms intervals, matching the readiness-wait cap used elsewhere.
+const MAX_DEPENDENCY_WAIT_ATTEMPTS: u32 = 120;
+
+// The URL the webview should load once every service is healthy: the
+// configured port of the service nothing else depends on (the one at the
+// end of the dependency chain), not a hardcoded literal.
+fn frontend_url(manifest: &[ServiceConfig]) -> String {
+    let depended_on: std::collections::HashSet<&str> = manifest
+        .iter()
+        .flat_map(|c| c.depends_on.iter().map(String::as_str))
+        .collect();
+
+    let entry = manifest
+        .iter()
+        .rev()
+        .find(|c| !depended_on.contains(c.name.as_str()))
+        .or_else(|| manifest.last())
+        .expect("services.yaml must define at least one service");
+
+    format!("http://127.0.0.1:{}", entry.port)
 }
 
-// Start the Python backend server
-fn start_backend() -> std::io::Result<Child> {
-    println!("Starting Python backend...");
-    
-    // Prefer project venv if present
-    let venv_python = "../backend/venv/bin/python";
-    if std::path::Path::new(venv_python).exists() {
-        match Command::new(venv_python)
-            .arg("-m")
-            .arg("uvicorn")
-            .arg("app.main_complete:app")
-            .arg("--host")
-            .arg("127.0.0.1")
-            .arg("--port")
-            .arg("8000")
-            .current_dir("../backend")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-        {
-            Ok(child) => {
-                println!("Backend started with project venv python");
-                return Ok(child);
-            }
-            Err(e) => {
-                println!("Failed to start backend with venv python: {}", e);
+// Start every service in the manifest, honoring `depends_on` by waiting for
+// a dependency's health check before spawning the dependent service.
+pub(crate) fn start_services(log_sink: &LogSink, manifest: &[ServiceConfig], state: &Arc<Mutex<AppState>>) {
+    for config in manifest {
+        for dep_name in &config.depends_on {
+            let dep_config = manifest.iter().find(|c| &c.name == dep_name).unwrap();
+            let mut attempts = 0;
+            while !futures::executor::block_on(is_service_ready(dep_config)) {
+                attempts += 1;
+                if attempts >= MAX_DEPENDENCY_WAIT_ATTEMPTS {
+                    eprintln!(
+                        "Dependency '{}' for service '{}' did not become healthy within {} seconds; starting anyway",
+                        dep_name, config.name, MAX_DEPENDENCY_WAIT_ATTEMPTS / 2
+                    );
+                    break;
+                }
+                thread::sleep(Duration::from_millis(500));
             }
         }
-    }
 
-    // Try different Python commands
-    let python_commands = ["python3", "python", "py"];
-    
-    for &python_cmd in &python_commands {
-        match Command::new(python_cmd)
-            .arg("-m")
-            .arg("uvicorn")
-            .arg("app.main_complete:app")
-            .arg("--host")
-            .arg("127.0.0.1")
-            .arg("--port")
-            .arg("8000")
-            .current_dir("../backend")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-        {
-            Ok(child) => {
-                println!("Backend started with {}", python_cmd);
-                return Ok(child);
-            }
-            Err(e) => {
-                println!("Failed to start backend with {}: {}", python_cmd, e);
-                continue;
-            }
+        if is_port_listening(config.port) && futures::executor::block_on(is_service_ready(config)) {
+            println!(
+                "Service '{}' is already serving on port {}, attaching instead of spawning",
+                config.name, config.port
+            );
+            let mut s = state.lock().unwrap();
+            s.externally_managed.insert(config.name.clone());
+            continue;
         }
-    }
-    
-    Err(std::io::Error::new(
-        std::io::ErrorKind::NotFound,
-        "Could not start Python backend - Python not found"
-    ))
-}
-
-// Start the React frontend dev server
-fn start_frontend() -> std::io::Result<Child> {
-    println!("Starting React frontend dev server...");
-
-    // Try npm, yarn, pnpm, bun
-    let commands: Vec<(&str, Vec<&str>)> = vec![
-        ("npm", vec!["run", "start"]),
-        ("yarn", vec!["start"]),
-        ("pnpm", vec!["start"]),
-        ("bun", vec!["run", "start"]),
-    ];
 
-    for (cmd, args) in commands {
-        let mut command = Command::new(cmd);
-        for a in &args { command.arg(a); }
-        let spawned = command
-            .current_dir("../frontend")
-            .env("BROWSER", "none") // prevent CRA from opening external browser
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn();
-        match spawned {
-            Ok(child) => {
-                println!("Frontend started with {} {}", cmd, args.join(" "));
-                return Ok(child);
-            }
-            Err(e) => {
-                println!("Failed to start frontend with {}: {}", cmd, e);
-                continue;
+        match spawn_service(config) {
+            Ok(mut child) => {
+                stream_child_output(log_sink.clone(), &config.name, &mut child);
+                let mut s = state.lock().unwrap();
+                s.children.insert(
+                    config.name.clone(),
+                    ManagedService {
+                        config: config.clone(),
+                        child,
+                        restart_count: 0,
+                        window_started_at: Instant::now(),
+                        last_exit_reason: None,
+                    },
+                );
             }
+            Err(e) => eprintln!("Failed to start service '{}': {}", config.name, e),
         }
     }
-
-    Err(std::io::Error::new(
-        std::io::ErrorKind::NotFound,
-        "Could not start React frontend - node package manager not found",
-    ))
-}
-
-struct AppState {
-    backend: Option<Child>,
-    frontend: Option<Child>,
 }
 
-// Wait for both services to be ready
-async fn wait_for_services(window: WebviewWindow) {
+// Wait for every configured service to report healthy, updating the
+// loading screen with which ones are still pending — and whether a given
+// service was attached to (already running) rather than spawned by us.
+async fn wait_for_services(window: WebviewWindow, manifest: Vec<ServiceConfig>, state: Arc<Mutex<AppState>>) {
     let mut attempts = 0;
     let max_attempts = 60; // 60 seconds max
-    
+
     while attempts < max_attempts {
-        let backend_ready = is_backend_ready().await;
-        let frontend_ready = is_frontend_ready().await;
-        
-        if backend_ready && frontend_ready {
-            println!("Both backend and frontend are ready!");
-            
-            // Load the frontend
-            let _ = window.navigate("http://127.0.0.1:3000".parse().unwrap());
+        let mut pending: Vec<&str> = Vec::new();
+        let mut statuses: Vec<String> = Vec::new();
+        for config in &manifest {
+            let ready = is_service_ready(config).await;
+            let attached = {
+                let s = state.lock().unwrap();
+                s.externally_managed.contains(&config.name)
+            };
+            let label = match (attached, ready) {
+                (true, _) => "attached",
+                (false, true) => "ready",
+                (false, false) => "starting",
+            };
+            statuses.push(format!("{} ({})", config.name, label));
+            if !ready {
+                pending.push(&config.name);
+            }
+        }
+
+        if pending.is_empty() {
+            println!("All services are ready!");
+            let _ = window.navigate(frontend_url(&manifest).parse().unwrap());
             return;
         }
-        
-        // Update loading message
-        let status = if backend_ready && !frontend_ready {
-            "Waiting for frontend..."
-        } else if !backend_ready && frontend_ready {
-            "Waiting for backend..."
-        } else {
-            "Starting services..."
-        };
-        
-        let _ = window.eval(&format!(r#"
+
+        let status = format!("Waiting for: {}...", statuses.join(", "));
+        let _ = window.eval(&format!(
+            r#"
             document.querySelector('p').textContent = '{}';
-        "#, status));
-        
+        "#,
+            status
+        ));
+
         tokio::time::sleep(Duration::from_secs(1)).await;
         attempts += 1;
-        println!("Waiting for services... ({}/{}) - Backend: {}, Frontend: {}", 
-                 attempts, max_attempts, backend_ready, frontend_ready);
+        println!(
+            "Waiting for services... ({}/{}) - pending: {}",
+            attempts,
+            max_attempts,
+            pending.join(", ")
+        );
     }
-    
+
     println!("Services failed to start within 60 seconds");
-    // Show error message in the window
     let _ = window.eval(r#"
         document.body.innerHTML = `
             <div style="display: flex; flex-direction: column; align-items: center; justify-content: center; height: 100vh; font-family: Arial, sans-serif;">
                 <h1 style="color: #f44336;">⚠️ Services Not Available</h1>
-                <p>Please make sure both backend and frontend are running:</p>
-                <p>Backend: <code>cd backend && python -m uvicorn app.main_complete:app --host 127.0.0.1 --port 8000</code></p>
-                <p>Frontend: <code>cd frontend && npm start</code></p>
+                <p>Please check services.yaml and make sure every listed service can start.</p>
                 <button onclick="location.reload()" style="margin-top: 20px; padding: 10px 20px; font-size: 16px; cursor: pointer;">
                     Try Again
                 </button>
@@ -186,22 +161,72 @@ async fn wait_for_services(window: WebviewWindow) {
 
 #[tauri::command]
 async fn check_backend_status() -> Result<bool, String> {
-    Ok(is_backend_ready().await)
+    let manifest = load_manifest(MANIFEST_PATH).map_err(|e| e.to_string())?;
+    let backend = manifest
+        .iter()
+        .find(|c| c.name == "backend")
+        .ok_or_else(|| "no 'backend' service in manifest".to_string())?;
+    Ok(is_service_ready(backend).await)
 }
 
 #[tauri::command]
 async fn check_frontend_status() -> Result<bool, String> {
-    Ok(is_frontend_ready().await)
+    let manifest = load_manifest(MANIFEST_PATH).map_err(|e| e.to_string())?;
+    let frontend = manifest
+        .iter()
+        .find(|c| c.name == "frontend")
+        .ok_or_else(|| "no 'frontend' service in manifest".to_string())?;
+    Ok(is_service_ready(frontend).await)
+}
+
+// Let the UI trigger a manual restart of a running (e.g. misbehaving)
+// service: it is killed and spawned afresh with its restart count reset.
+// Only services the supervisor currently tracks can be restarted; one that
+// is sitting out a restart backoff, or that the supervisor has given up
+// on, is not tracked and gets a "no such service" error.
+#[tauri::command]
+async fn restart_service(
+    app_handle: tauri::AppHandle,
+    state: tauri::State<'_, Arc<Mutex<AppState>>>,
+    name: String,
+) -> Result<(), String> {
+    let log_sink = LogSink::TauriEvent(app_handle);
+    force_restart(&log_sink, &state, &name)
+}
+
+// Let the UI trigger the same coordinated, dependency-ordered shutdown
+// that runs on window close.
+#[tauri::command]
+async fn shutdown_all(
+    state: tauri::State<'_, Arc<Mutex<AppState>>>,
+    manifest: tauri::State<'_, Vec<ServiceConfig>>,
+) -> Result<(), String> {
+    shutdown_services(&state, &manifest);
+    Ok(())
 }
 
 fn main() {
+    let args = Cli::parse();
+    let manifest = load_manifest(MANIFEST_PATH).expect("failed to load services.yaml");
+
+    if args.headless {
+        cli::run_headless(manifest);
+        return;
+    }
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
-        .invoke_handler(tauri::generate_handler![check_backend_status, check_frontend_status])
-        .setup(|app| {
+        .invoke_handler(tauri::generate_handler![
+            check_backend_status,
+            check_frontend_status,
+            restart_service,
+            shutdown_all
+        ])
+        .setup(move |app| {
             let window = app.get_webview_window("main").unwrap();
-            
-            // Show loading screen initially
+
+            // Show loading screen initially, with a live log panel fed by
+            // `service-log` events emitted as children print to stdout/stderr.
             let _ = window.eval(r#"
                 document.body.innerHTML = `
                     <div style="display: flex; flex-direction: column; align-items: center; justify-content: center; height: 100vh; font-family: Arial, sans-serif; background: linear-gradient(135deg, #667eea 0%, #764ba2 100%);">
@@ -213,6 +238,7 @@ fn main() {
                             <p>Starting application...</p>
                             <p style="font-size: 14px; opacity: 0.8;">Please wait while we initialize the system</p>
                         </div>
+                        <pre id="service-log" style="width: 80%; max-width: 800px; height: 200px; margin-top: 20px; padding: 10px; overflow-y: auto; background: rgba(0,0,0,0.35); color: #e0e0e0; font-size: 12px; border-radius: 6px; text-align: left;"></pre>
                         <style>
                             @keyframes spin {
                                 0% { transform: rotate(0deg); }
@@ -221,60 +247,62 @@ fn main() {
                         </style>
                     </div>
                 `;
+                if (window.__TAURI__ && window.__TAURI__.event) {
+                    window.__TAURI__.event.listen('service-log', (event) => {
+                        const el = document.getElementById('service-log');
+                        if (!el) return;
+                        const { service, stream, line } = event.payload;
+                        el.textContent += `[${service}:${stream}] ${line}\n`;
+                        el.scrollTop = el.scrollHeight;
+                    });
+                }
             "#);
-            
+
             // Prepare shared state for child process handles
-            let state = Arc::new(Mutex::new(AppState { backend: None, frontend: None }));
+            let state = Arc::new(Mutex::new(AppState::new()));
 
-            // Start backend
-            {
-                let state_clone = Arc::clone(&state);
-                thread::spawn(move || {
-                    match start_backend() {
-                        Ok(child) => {
-                            let mut s = state_clone.lock().unwrap();
-                            s.backend = Some(child);
-                        }
-                        Err(e) => eprintln!("Failed to start backend: {}", e),
-                    }
-                });
-            }
+            // Make state and the manifest reachable from #[tauri::command]s
+            // (restart_service, shutdown_all) via tauri::State.
+            app.manage(Arc::clone(&state));
+            app.manage(manifest.clone());
 
-            // Start frontend
+            // Start every configured service, then watch them for crashes.
+            // `start_services` blocks on health checks via
+            // `futures::executor::block_on`, which needs an entered Tokio
+            // runtime to drive `reqwest`'s networking — a bare
+            // `std::thread::spawn` doesn't have one and panics. Run it on
+            // Tauri's async runtime's blocking pool instead, the same way
+            // `cli.rs`'s headless path uses `tokio::task::spawn_blocking`.
             {
                 let state_clone = Arc::clone(&state);
-                thread::spawn(move || {
-                    match start_frontend() {
-                        Ok(child) => {
-                            let mut s = state_clone.lock().unwrap();
-                            s.frontend = Some(child);
-                        }
-                        Err(e) => eprintln!("Failed to start frontend: {}", e),
-                    }
+                let manifest_clone = manifest.clone();
+                let log_sink = LogSink::TauriEvent(app.handle().clone());
+                let log_sink_for_supervisor = log_sink.clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    start_services(&log_sink, &manifest_clone, &state_clone);
+                    start_supervisor(log_sink_for_supervisor, state_clone);
                 });
             }
-            
-            // Wait for both services to be ready
+
+            // Wait for all services to be ready
             let window_clone = window.clone();
+            let manifest_for_wait = manifest.clone();
+            let state_for_wait = Arc::clone(&state);
             tauri::async_runtime::spawn(async move {
-                wait_for_services(window_clone).await;
+                wait_for_services(window_clone, manifest_for_wait, state_for_wait).await;
             });
-            
-            // Ensure child processes are terminated when app exits
+
+            // Run the coordinated, dependency-ordered shutdown when the
+            // window is closing instead of just SIGKILLing everything.
             let app_handle = app.handle();
             let state_for_cleanup = Arc::clone(&state);
+            let manifest_for_cleanup = manifest.clone();
             app_handle.once_global("tauri://close-requested", move |_| {
-                let mut s = state_for_cleanup.lock().unwrap();
-                if let Some(child) = s.backend.as_mut() {
-                    let _ = child.kill();
-                }
-                if let Some(child) = s.frontend.as_mut() {
-                    let _ = child.kill();
-                }
+                shutdown_services(&state_for_cleanup, &manifest_for_cleanup);
             });
-            
+
             Ok(())
         })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
-}
\ No newline at end of file
+}
@@ -0,0 +1,14 @@
+// Detects services that are already running (e.g. a developer's own
+// `uvicorn`/dev-server instance) so the launcher doesn't spawn a duplicate
+// that then fails to bind the same port.
+
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+const PROBE_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// True if something is already accepting connections on 127.0.0.1:port.
+pub fn is_port_listening(port: u16) -> bool {
+    let addr: SocketAddr = ([127, 0, 0, 1], port).into();
+    TcpStream::connect_timeout(&addr, PROBE_TIMEOUT).is_ok()
+}
@@ -0,0 +1,135 @@
+// Service manifest: defines what gets spawned instead of hardcoding the
+// backend/frontend invocations directly in main.rs.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct HealthCheck {
+    pub url: String,
+    pub expected_status: u16,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ServiceConfig {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    pub cwd: String,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    pub port: u16,
+    pub health_check: HealthCheck,
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// How long to wait after a graceful-stop request before force-killing
+    /// this service. Falls back to a repo-wide default when unset.
+    #[serde(default)]
+    pub shutdown_grace_period_secs: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    services: Vec<ServiceConfig>,
+}
+
+#[derive(Debug)]
+pub enum ManifestError {
+    Io(std::io::Error),
+    Parse(serde_yaml::Error),
+    UnknownDependency { service: String, depends_on: String },
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::Io(e) => write!(f, "could not read services manifest: {}", e),
+            ManifestError::Parse(e) => write!(f, "could not parse services manifest: {}", e),
+            ManifestError::UnknownDependency { service, depends_on } => write!(
+                f,
+                "service '{}' depends_on unknown service '{}'",
+                service, depends_on
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+/// Load and validate the service manifest. Services must be listed in an
+/// order where each entry's `depends_on` only refers to services that
+/// appear earlier in the list.
+pub fn load_manifest<P: AsRef<Path>>(path: P) -> Result<Vec<ServiceConfig>, ManifestError> {
+    let raw = fs::read_to_string(path).map_err(ManifestError::Io)?;
+    let manifest: Manifest = serde_yaml::from_str(&raw).map_err(ManifestError::Parse)?;
+
+    let mut seen: Vec<String> = Vec::new();
+    for service in &manifest.services {
+        for dep in &service.depends_on {
+            if !seen.contains(dep) {
+                return Err(ManifestError::UnknownDependency {
+                    service: service.name.clone(),
+                    depends_on: dep.clone(),
+                });
+            }
+        }
+        seen.push(service.name.clone());
+    }
+
+    Ok(manifest.services)
+}
+
+/// Spawn a service according to its config. This replaces the old
+/// `start_backend`/`start_frontend` functions with a single generic path.
+/// Note that this drops their interpreter/package-manager fallback probing
+/// (venv python -> python3/python/py, npm/yarn/pnpm/bun) in favor of a
+/// single configured `command` — see the comment in services.yaml.
+pub fn spawn_service(config: &ServiceConfig) -> std::io::Result<Child> {
+    println!("Starting service '{}'...", config.name);
+
+    let mut command = Command::new(&config.command);
+    command
+        .args(&config.args)
+        .current_dir(&config.cwd)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    for (key, value) in &config.env {
+        command.env(key, value);
+    }
+
+    // Put the child in its own process group so a later graceful-stop via
+    // CTRL_BREAK_EVENT (see shutdown.rs) targets just this process instead
+    // of silently no-oping against the launcher's own group.
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
+        command.creation_flags(CREATE_NEW_PROCESS_GROUP);
+    }
+
+    match command.spawn() {
+        Ok(child) => {
+            println!("Service '{}' started ({} {})", config.name, config.command, config.args.join(" "));
+            Ok(child)
+        }
+        Err(e) => {
+            println!("Failed to start service '{}': {}", config.name, e);
+            Err(e)
+        }
+    }
+}
+
+/// Check a service's health check URL against its expected status.
+pub async fn is_service_ready(config: &ServiceConfig) -> bool {
+    match reqwest::get(&config.health_check.url).await {
+        Ok(response) => response.status().as_u16() == config.health_check.expected_status,
+        Err(_) => false,
+    }
+}
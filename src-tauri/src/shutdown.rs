@@ -0,0 +1,101 @@
+// Coordinated shutdown: ask each service to terminate gracefully, in
+// reverse dependency order, before resorting to a hard kill.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::services::ServiceConfig;
+use crate::supervisor::AppState;
+
+const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+// Returns whether the graceful-stop request was actually delivered.
+#[cfg(unix)]
+fn request_graceful_stop(pid: u32) -> bool {
+    use nix::sys::signal::{kill, Signal};
+    use nix::unistd::Pid;
+
+    kill(Pid::from_raw(pid as i32), Signal::SIGTERM).is_ok()
+}
+
+#[cfg(windows)]
+fn request_graceful_stop(pid: u32) -> bool {
+    // Send a CTRL_BREAK_EVENT to the process group. This only reaches the
+    // child if it was spawned with CREATE_NEW_PROCESS_GROUP (services.rs
+    // sets this), otherwise the event would hit our own group too.
+    let delivered = unsafe {
+        winapi::um::wincon::GenerateConsoleCtrlEvent(winapi::um::wincon::CTRL_BREAK_EVENT, pid)
+    };
+    delivered != 0
+}
+
+/// Stop every managed, non-externally-managed service, dependents before
+/// their dependencies, giving each one a grace period to exit on its own
+/// before it is force-killed.
+pub fn shutdown_services(state: &Arc<Mutex<AppState>>, manifest: &[ServiceConfig]) {
+    {
+        let s = state.lock().unwrap();
+        s.shutting_down.store(true, std::sync::atomic::Ordering::SeqCst);
+        let _ = s.shutdown_tx.send(());
+    }
+
+    for config in manifest.iter().rev() {
+        let grace_period = config
+            .shutdown_grace_period_secs
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_GRACE_PERIOD);
+
+        let pid = {
+            let s = state.lock().unwrap();
+            if s.externally_managed.contains(&config.name) {
+                continue;
+            }
+            s.children.get(&config.name).map(|managed| managed.child.id())
+        };
+
+        let pid = match pid {
+            Some(pid) => pid,
+            None => continue,
+        };
+
+        println!("Stopping service '{}' (pid {})...", config.name, pid);
+        if !request_graceful_stop(pid) {
+            eprintln!(
+                "Could not deliver a graceful-stop signal to service '{}' (pid {}), will wait out the grace period then kill",
+                config.name, pid
+            );
+        }
+
+        let deadline = Instant::now() + grace_period;
+        let exited = loop {
+            let mut s = state.lock().unwrap();
+            let still_running = match s.children.get_mut(&config.name) {
+                Some(managed) => matches!(managed.child.try_wait(), Ok(None)),
+                None => false,
+            };
+            drop(s);
+
+            if !still_running {
+                break true;
+            }
+            if Instant::now() >= deadline {
+                break false;
+            }
+            thread::sleep(Duration::from_millis(200));
+        };
+
+        let mut s = state.lock().unwrap();
+        if let Some(mut managed) = s.children.remove(&config.name) {
+            if !exited {
+                println!(
+                    "Service '{}' did not exit within {:?}, killing",
+                    config.name, grace_period
+                );
+                let _ = managed.child.kill();
+            } else {
+                println!("Service '{}' stopped", config.name);
+            }
+        }
+    }
+}
@@ -0,0 +1,235 @@
+// Watches spawned children after launch and restarts them on an
+// unexpected exit, with a capped exponential backoff so a crash-looping
+// service doesn't spin the CPU or hammer its own dependencies.
+
+use std::collections::{HashMap, HashSet};
+use std::process::Child;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tokio::sync::broadcast;
+
+use crate::logs::{stream_child_output, LogSink, ServiceExitedEvent, ServiceFailedEvent};
+use crate::services::{spawn_service, ServiceConfig};
+
+const MAX_RESTARTS_PER_WINDOW: u32 = 5;
+const RESTART_WINDOW: Duration = Duration::from_secs(60);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct ManagedService {
+    pub config: ServiceConfig,
+    pub child: Child,
+    pub restart_count: u32,
+    pub window_started_at: Instant,
+    pub last_exit_reason: Option<String>,
+}
+
+pub struct AppState {
+    pub children: HashMap<String, ManagedService>,
+    // Services that were already running before the launcher started; we
+    // neither restart nor kill these.
+    pub externally_managed: HashSet<String>,
+    // Broadcast so the supervisor stops restarting once a coordinated
+    // shutdown has begun.
+    pub shutdown_tx: broadcast::Sender<()>,
+    // Set by `shutdown_services` before it starts stopping anything, so a
+    // restart already in its backoff sleep can notice and bail instead of
+    // respawning (and orphaning) a child after shutdown has completed.
+    pub shutting_down: Arc<AtomicBool>,
+}
+
+impl AppState {
+    pub fn new() -> Self {
+        let (shutdown_tx, _) = broadcast::channel(1);
+        AppState {
+            children: HashMap::new(),
+            externally_managed: HashSet::new(),
+            shutdown_tx,
+            shutting_down: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+fn backoff_for(restart_count: u32) -> Duration {
+    let secs = 1u64.saturating_shl(restart_count.min(5));
+    Duration::from_secs(secs).min(MAX_BACKOFF)
+}
+
+/// Spawn the background thread that polls every managed child and
+/// restarts it (with backoff) if it exits unexpectedly.
+pub fn start_supervisor(log_sink: LogSink, state: Arc<Mutex<AppState>>) {
+    let mut shutdown_rx = {
+        let s = state.lock().unwrap();
+        s.shutdown_tx.subscribe()
+    };
+
+    thread::spawn(move || loop {
+        if shutdown_rx.try_recv().is_ok() {
+            println!("Supervisor stopping: shutdown in progress");
+            break;
+        }
+
+        thread::sleep(POLL_INTERVAL);
+
+        let names: Vec<String> = {
+            let s = state.lock().unwrap();
+            s.children.keys().cloned().collect()
+        };
+
+        for name in names {
+            let exited_with = {
+                let mut s = state.lock().unwrap();
+                match s.children.get_mut(&name) {
+                    Some(managed) => match managed.child.try_wait() {
+                        Ok(Some(status)) => Some((status.to_string(), status.code(), status.success())),
+                        Ok(None) => None,
+                        Err(e) => {
+                            eprintln!("Failed to poll service '{}': {}", name, e);
+                            None
+                        }
+                    },
+                    None => None,
+                }
+            };
+
+            if let Some((reason, exit_code, clean)) = exited_with {
+                log_sink.publish_exited(ServiceExitedEvent {
+                    service: name.clone(),
+                    exit_code,
+                    clean,
+                });
+                // A clean (zero) exit is not a crash: stop tracking the
+                // service instead of restarting it.
+                if clean {
+                    state.lock().unwrap().children.remove(&name);
+                    continue;
+                }
+                // Run the restart (including its backoff sleep) on its own
+                // thread so one crash-looping service doesn't block this
+                // loop from polling/restarting every other service for up
+                // to MAX_BACKOFF seconds.
+                let log_sink = log_sink.clone();
+                let state = Arc::clone(&state);
+                thread::spawn(move || restart_service(&log_sink, &state, &name, reason));
+            }
+        }
+    });
+}
+
+fn restart_service(log_sink: &LogSink, state: &Arc<Mutex<AppState>>, name: &str, exit_reason: String) {
+    let (config, restart_count, window_started_at) = {
+        let mut s = state.lock().unwrap();
+        let managed = match s.children.remove(name) {
+            Some(m) => m,
+            None => return,
+        };
+
+        let now = Instant::now();
+        let (restart_count, window_started_at) = if now.duration_since(managed.window_started_at) > RESTART_WINDOW {
+            (0, now)
+        } else {
+            (managed.restart_count, managed.window_started_at)
+        };
+
+        eprintln!(
+            "Service '{}' exited unexpectedly ({}), restart {} in this window",
+            name, exit_reason, restart_count + 1
+        );
+
+        (managed.config, restart_count, window_started_at)
+    };
+
+    if restart_count >= MAX_RESTARTS_PER_WINDOW {
+        eprintln!(
+            "Service '{}' exceeded {} restarts within {:?}, giving up",
+            name, MAX_RESTARTS_PER_WINDOW, RESTART_WINDOW
+        );
+        log_sink.publish_failed(ServiceFailedEvent {
+            service: name.to_string(),
+            restart_count,
+        });
+        return;
+    }
+
+    if is_shutting_down(state) {
+        println!("Shutdown in progress, not restarting '{}'", name);
+        return;
+    }
+
+    thread::sleep(backoff_for(restart_count));
+
+    // A coordinated shutdown may have started and already finished while
+    // we were sleeping off the backoff; don't resurrect a service after
+    // that, or it would be spawned untracked and never get stopped.
+    if is_shutting_down(state) {
+        println!("Shutdown started during restart backoff, not respawning '{}'", name);
+        return;
+    }
+
+    match spawn_service(&config) {
+        Ok(mut child) => {
+            stream_child_output(log_sink.clone(), &config.name, &mut child);
+            let mut s = state.lock().unwrap();
+            s.children.insert(
+                name.to_string(),
+                ManagedService {
+                    config,
+                    child,
+                    restart_count: restart_count + 1,
+                    window_started_at,
+                    last_exit_reason: Some(exit_reason),
+                },
+            );
+        }
+        Err(e) => eprintln!("Failed to restart service '{}': {}", name, e),
+    }
+}
+
+fn is_shutting_down(state: &Arc<Mutex<AppState>>) -> bool {
+    state.lock().unwrap().shutting_down.load(Ordering::SeqCst)
+}
+
+/// Immediately restart a named service, bypassing the backoff/restart-cap
+/// bookkeeping. Used by the `restart_service` Tauri command for a manual,
+/// user-triggered restart.
+pub fn force_restart(log_sink: &LogSink, state: &Arc<Mutex<AppState>>, name: &str) -> Result<(), String> {
+    if is_shutting_down(state) {
+        return Err("shutdown in progress".to_string());
+    }
+
+    let config = {
+        let mut s = state.lock().unwrap();
+        if s.externally_managed.contains(name) {
+            return Err(format!("'{}' is externally managed, not under our control", name));
+        }
+        match s.children.remove(name) {
+            Some(mut managed) => {
+                let _ = managed.child.kill();
+                managed.config
+            }
+            None => return Err(format!("no such service '{}'", name)),
+        }
+    };
+
+    match spawn_service(&config) {
+        Ok(mut child) => {
+            stream_child_output(log_sink.clone(), &config.name, &mut child);
+            let mut s = state.lock().unwrap();
+            s.children.insert(
+                name.to_string(),
+                ManagedService {
+                    config,
+                    child,
+                    restart_count: 0,
+                    window_started_at: Instant::now(),
+                    last_exit_reason: None,
+                },
+            );
+            Ok(())
+        }
+        Err(e) => Err(format!("failed to restart '{}': {}", name, e)),
+    }
+}